@@ -0,0 +1,108 @@
+use actix_web::{dev::Payload, http::header, web, FromRequest, HttpRequest};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use std::future::{ready, Ready};
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::Error;
+
+// Claims carried by the signed JWT: the subject is the user's id and `exp`
+// is a unix timestamp `now + JWT_MAXAGE`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TokenClaims {
+    pub sub: Uuid,
+    pub iat: usize,
+    pub exp: usize,
+}
+
+// Sign a token for `user_id`, expiring `maxage` seconds from now.
+pub fn create_token(user_id: Uuid, secret: &str, maxage: i64) -> jsonwebtoken::errors::Result<String> {
+    let now = chrono::Utc::now();
+    let claims = TokenClaims {
+        sub: user_id,
+        iat: now.timestamp() as usize,
+        exp: (now + chrono::Duration::seconds(maxage)).timestamp() as usize,
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )
+}
+
+// Verify a token and return its claims.
+pub fn decode_token(token: &str, secret: &str) -> jsonwebtoken::errors::Result<TokenClaims> {
+    decode::<TokenClaims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &Validation::default(),
+    )
+    .map(|data| data.claims)
+}
+
+// Extractor that resolves the authenticated user id from an
+// `Authorization: Bearer <token>` header or an `auth` cookie.
+pub struct AuthenticatedUser {
+    pub user_id: Uuid,
+}
+
+impl FromRequest for AuthenticatedUser {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let config = match req.app_data::<web::Data<Config>>() {
+            Some(config) => config,
+            None => {
+                return ready(Err(actix_web::error::ErrorInternalServerError(
+                    "missing config",
+                )))
+            }
+        };
+
+        let token = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer ").map(|t| t.to_string()))
+            .or_else(|| req.cookie("auth").map(|c| c.value().to_string()));
+
+        let token = match token {
+            Some(token) => token,
+            None => {
+                return ready(Err(
+                    Error::Unauthorized("missing authentication token").into(),
+                ))
+            }
+        };
+
+        match decode_token(&token, &config.jwt_secret) {
+            Ok(claims) => ready(Ok(AuthenticatedUser {
+                user_id: claims.sub,
+            })),
+            Err(_) => ready(Err(Error::Unauthorized("invalid token").into())),
+        }
+    }
+}
+
+// ---- request payloads ----
+
+#[derive(Deserialize)]
+pub struct RegisterUser {
+    pub name: String,
+    pub email: String,
+    pub password: String,
+}
+
+#[derive(Deserialize)]
+pub struct LoginUser {
+    pub email: String,
+    pub password: String,
+}
+
+#[derive(Serialize)]
+pub struct TokenResponse {
+    pub token: String,
+}
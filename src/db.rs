@@ -0,0 +1,166 @@
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::Error;
+use crate::{CreateUser, ListUsers, UpdateUser, User};
+
+// Largest page a caller may request; larger `limit` values are clamped to this.
+const MAX_LIMIT: i64 = 100;
+const DEFAULT_LIMIT: i64 = 20;
+
+// Data-access layer for the `users` table. Handlers call into these async
+// functions and stay free of SQL, so new entities can reuse the same pattern
+// without duplicating query plumbing.
+
+// A paginated slice of users together with the total row count so clients can
+// drive page controls without a second request.
+#[derive(Serialize)]
+pub struct UserPage {
+    pub data: Vec<User>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+// Fetch a page of users, applying an optional name/email search and a
+// whitelisted sort. `limit` is clamped to `MAX_LIMIT` and both bounds default
+// sensibly when absent.
+pub async fn list_users(pool: &PgPool, params: &ListUsers) -> Result<UserPage, Error> {
+    // Only ever interpolate whitelisted identifiers into the SQL string; the
+    // search term and bounds are passed as bind parameters.
+    let sort_by = match params.sort_by.as_deref() {
+        Some("name") => "name",
+        Some("email") => "email",
+        _ => "id",
+    };
+    let order = match params.order.as_deref() {
+        Some(o) if o.eq_ignore_ascii_case("desc") => "DESC",
+        _ => "ASC",
+    };
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = params.offset.unwrap_or(0).max(0);
+    let search = params.search.as_deref().filter(|s| !s.is_empty());
+
+    let (data, total) = if let Some(term) = search {
+        let pattern = format!("%{}%", term);
+        let total: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM users WHERE name ILIKE $1 OR email ILIKE $1",
+        )
+        .bind(&pattern)
+        .fetch_one(pool)
+        .await?;
+
+        let sql = format!(
+            "SELECT * FROM users WHERE name ILIKE $1 OR email ILIKE $1 \
+             ORDER BY {} {} LIMIT $2 OFFSET $3",
+            sort_by, order
+        );
+        let data = sqlx::query_as::<_, User>(&sql)
+            .bind(&pattern)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(pool)
+            .await?;
+        (data, total)
+    } else {
+        let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+            .fetch_one(pool)
+            .await?;
+
+        let sql = format!(
+            "SELECT * FROM users ORDER BY {} {} LIMIT $1 OFFSET $2",
+            sort_by, order
+        );
+        let data = sqlx::query_as::<_, User>(&sql)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(pool)
+            .await?;
+        (data, total)
+    };
+
+    Ok(UserPage {
+        data,
+        total,
+        limit,
+        offset,
+    })
+}
+
+// Fetch a single user, mapping a missing row to `Error::NotFound`.
+pub async fn get_user(pool: &PgPool, id: Uuid) -> Result<User, Error> {
+    sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(Error::NotFound)
+}
+
+// Look up a user by email for the login path, returning `None` when no account
+// matches so the caller can decide the appropriate status code.
+pub async fn get_user_by_email(pool: &PgPool, email: &str) -> Result<Option<User>, Error> {
+    let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = $1")
+        .bind(email)
+        .fetch_optional(pool)
+        .await?;
+    Ok(user)
+}
+
+// Insert a new user from an already-hashed password, returning the stored row
+// (with the hash cleared so it never leaves the data layer).
+pub async fn create_user(pool: &PgPool, data: &CreateUser, password_hash: &str) -> Result<User, Error> {
+    let id = Uuid::new_v4();
+    sqlx::query("INSERT INTO users (id, name, email, password_hash) VALUES ($1, $2, $3, $4)")
+        .bind(id)
+        .bind(&data.name)
+        .bind(&data.email)
+        .bind(password_hash)
+        .execute(pool)
+        .await
+        .map_err(Error::from_unique)?;
+
+    Ok(User {
+        id,
+        name: data.name.clone(),
+        email: data.email.clone(),
+        password_hash: String::new(),
+    })
+}
+
+// Apply a partial update, leaving absent fields untouched.
+pub async fn update_user(pool: &PgPool, id: Uuid, data: &UpdateUser) -> Result<User, Error> {
+    let old_user = get_user(pool, id).await?;
+
+    let new_name = data.name.clone().unwrap_or(old_user.name);
+    let new_email = data.email.clone().unwrap_or(old_user.email);
+
+    sqlx::query("UPDATE users SET name = $1, email = $2 WHERE id = $3")
+        .bind(new_name.clone())
+        .bind(new_email.clone())
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(Error::from_unique)?;
+
+    Ok(User {
+        id,
+        name: new_name,
+        email: new_email,
+        password_hash: String::new(),
+    })
+}
+
+// Delete a user, mapping a no-op delete to `Error::NotFound`.
+pub async fn delete_user(pool: &PgPool, id: Uuid) -> Result<(), Error> {
+    let result = sqlx::query("DELETE FROM users WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(Error::NotFound);
+    }
+
+    Ok(())
+}
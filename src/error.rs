@@ -0,0 +1,72 @@
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use serde::Serialize;
+use thiserror::Error;
+
+// Application-wide error type. Every handler returns `Result<_, Error>` so the
+// `?` operator can bubble failures up to a single `ResponseError` impl that
+// renders a structured JSON body.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("database error")]
+    Database(#[from] sqlx::Error),
+
+    #[error("resource not found")]
+    NotFound,
+
+    #[error("{0}")]
+    Unauthorized(&'static str),
+
+    #[error("{0}")]
+    Forbidden(&'static str),
+
+    #[error("invalid email address")]
+    EmailInvalid,
+
+    #[error("email already in use")]
+    EmailTaken,
+
+    #[error("{0}")]
+    Validation(String),
+}
+
+impl Error {
+    // Map a sqlx error raised by an insert/update, translating a Postgres
+    // unique-constraint violation (SQLSTATE 23505) into a clean 409 instead of
+    // surfacing it as a generic database error.
+    pub fn from_unique(err: sqlx::Error) -> Error {
+        if let sqlx::Error::Database(ref db_err) = err {
+            if db_err.code().as_deref() == Some("23505") {
+                return Error::EmailTaken;
+            }
+        }
+        Error::Database(err)
+    }
+}
+
+// Shape of the JSON error body returned to clients.
+#[derive(Serialize)]
+struct ErrorBody {
+    status: &'static str,
+    message: String,
+}
+
+impl ResponseError for Error {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Error::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Error::NotFound => StatusCode::NOT_FOUND,
+            Error::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Error::Forbidden(_) => StatusCode::FORBIDDEN,
+            Error::EmailInvalid => StatusCode::UNPROCESSABLE_ENTITY,
+            Error::EmailTaken => StatusCode::CONFLICT,
+            Error::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(ErrorBody {
+            status: "error",
+            message: self.to_string(),
+        })
+    }
+}
@@ -1,21 +1,46 @@
-use actix_web::{web, App, HttpServer, Responder, HttpResponse};
+mod auth;
+mod config;
+mod db;
+mod error;
+mod password;
+mod session;
+
+use actix_web::cookie::Cookie;
+use actix_web::{web, App, HttpServer, HttpResponse};
+use email_address::EmailAddress;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use sqlx::{PgPool, FromRow};
 use dotenvy::dotenv;
-use std::env;
+
+use auth::{AuthenticatedUser, LoginUser, RegisterUser, TokenResponse};
+use config::Config;
+use error::Error;
+use session::Session;
 
 #[derive(Serialize, Deserialize, FromRow)]
 struct User {
     id: Uuid,
     name: String,
     email: String,
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    password_hash: String,
+}
+
+impl User {
+    // Fetch a user by id, used by `Session::user` to resolve the owner of an
+    // opaque cookie's session.
+    async fn get_id(pool: &PgPool, id: Uuid) -> Result<User, Error> {
+        db::get_user(pool, id).await
+    }
 }
 
 #[derive(Deserialize)]
 struct CreateUser {
     name: String,
     email: String,
+    password: String,
 }
 
 #[derive(Deserialize)]
@@ -24,106 +49,140 @@ struct UpdateUser {
     email: Option<String>,
 }
 
+// Query parameters for the paginated `GET /users` listing. All fields are
+// optional; the data layer clamps and whitelists them before building SQL.
+#[derive(Deserialize)]
+struct ListUsers {
+    limit: Option<i64>,
+    offset: Option<i64>,
+    sort_by: Option<String>,
+    order: Option<String>,
+    search: Option<String>,
+}
+
 // GET /users
-async fn get_users(db: web::Data<PgPool>) -> impl Responder {
-    let result = sqlx::query_as::<_, User>("SELECT * FROM users")
-        .fetch_all(db.get_ref())
-        .await;
-
-    match result {
-        Ok(users) => HttpResponse::Ok().json(users),
-        Err(_) => HttpResponse::InternalServerError().body("Failed to fetch users"),
-    }
+async fn get_users(pool: web::Data<PgPool>, params: web::Query<ListUsers>) -> Result<HttpResponse, Error> {
+    let page = db::list_users(pool.get_ref(), &params).await?;
+    Ok(HttpResponse::Ok().json(page))
 }
 
 // GET /users/{id}
-async fn get_user(path: web::Path<Uuid>, db: web::Data<PgPool>) -> impl Responder {
-    let id = path.into_inner();
-    let result = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
-        .bind(id)
-        .fetch_optional(db.get_ref())
-        .await;
-
-    match result {
-        Ok(Some(user)) => HttpResponse::Ok().json(user),
-        Ok(None) => HttpResponse::NotFound().body("User not found"),
-        Err(_) => HttpResponse::InternalServerError().body("Error fetching user"),
-    }
+async fn get_user(path: web::Path<Uuid>, pool: web::Data<PgPool>) -> Result<HttpResponse, Error> {
+    let user = db::get_user(pool.get_ref(), path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(user))
 }
 
 // POST /users
-async fn create_user(db: web::Data<PgPool>, user: web::Json<CreateUser>) -> impl Responder {
-    let id = Uuid::new_v4();
-    let result = sqlx::query("INSERT INTO users (id, name, email) VALUES ($1, $2, $3)")
-        .bind(id)
-        .bind(&user.name)
-        .bind(&user.email)
-        .execute(db.get_ref())
-        .await;
-
-    match result {
-        Ok(_) => HttpResponse::Created().json(User {
-            id,
-            name: user.name.clone(),
-            email: user.email.clone(),
-        }),
-        Err(_) => HttpResponse::InternalServerError().body("Failed to insert user"),
+async fn create_user(pool: web::Data<PgPool>, user: web::Json<CreateUser>) -> Result<HttpResponse, Error> {
+    if !EmailAddress::is_valid(&user.email) {
+        return Err(Error::EmailInvalid);
     }
+
+    let hash = password::hash(&user.password)
+        .map_err(|_| Error::Validation("failed to hash password".into()))?;
+
+    let created = db::create_user(pool.get_ref(), &user, &hash).await?;
+    Ok(HttpResponse::Created().json(created))
 }
 
 // PUT /users/{id}
-async fn update_user(path: web::Path<Uuid>, db: web::Data<PgPool>, user: web::Json<UpdateUser>) -> impl Responder {
+async fn update_user(path: web::Path<Uuid>, pool: web::Data<PgPool>, user: web::Json<UpdateUser>, authed: AuthenticatedUser) -> Result<HttpResponse, Error> {
     let id = path.into_inner();
 
-    let existing = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
-        .bind(id)
-        .fetch_optional(db.get_ref())
-        .await;
-
-    if let Ok(Some(old_user)) = existing {
-        let new_name = user.name.clone().unwrap_or(old_user.name);
-        let new_email = user.email.clone().unwrap_or(old_user.email);
-
-        let result = sqlx::query("UPDATE users SET name = $1, email = $2 WHERE id = $3")
-            .bind(new_name.clone())
-            .bind(new_email.clone())
-            .bind(id)
-            .execute(db.get_ref())
-            .await;
-
-        match result {
-            Ok(_) => HttpResponse::Ok().json(User {
-                id,
-                name: new_name,
-                email: new_email,
-            }),
-            Err(_) => HttpResponse::InternalServerError().body("Failed to update user"),
+    if authed.user_id != id {
+        return Err(Error::Forbidden("You can only modify your own account"));
+    }
+
+    if let Some(email) = &user.email {
+        if !EmailAddress::is_valid(email) {
+            return Err(Error::EmailInvalid);
         }
-    } else {
-        HttpResponse::NotFound().body("User not found")
     }
+
+    let updated = db::update_user(pool.get_ref(), id, &user).await?;
+    Ok(HttpResponse::Ok().json(updated))
 }
 
 // DELETE /users/{id}
-async fn delete_user(path: web::Path<Uuid>, db: web::Data<PgPool>) -> impl Responder {
+async fn delete_user(path: web::Path<Uuid>, pool: web::Data<PgPool>, authed: AuthenticatedUser) -> Result<HttpResponse, Error> {
     let id = path.into_inner();
-    let result = sqlx::query("DELETE FROM users WHERE id = $1")
-        .bind(id)
-        .execute(db.get_ref())
-        .await;
-
-    match result {
-        Ok(r) if r.rows_affected() > 0 => HttpResponse::Ok().body("User deleted"),
-        Ok(_) => HttpResponse::NotFound().body("User not found"),
-        Err(_) => HttpResponse::InternalServerError().body("Failed to delete user"),
+
+    if authed.user_id != id {
+        return Err(Error::Forbidden("You can only delete your own account"));
     }
+
+    db::delete_user(pool.get_ref(), id).await?;
+    Ok(HttpResponse::Ok().body("User deleted"))
+}
+
+// POST /auth/register
+async fn register(pool: web::Data<PgPool>, body: web::Json<RegisterUser>) -> Result<HttpResponse, Error> {
+    if !EmailAddress::is_valid(&body.email) {
+        return Err(Error::EmailInvalid);
+    }
+
+    let hash = password::hash(&body.password)
+        .map_err(|_| Error::Validation("failed to hash password".into()))?;
+
+    let new_user = CreateUser {
+        name: body.name.clone(),
+        email: body.email.clone(),
+        password: body.password.clone(),
+    };
+    let created = db::create_user(pool.get_ref(), &new_user, &hash).await?;
+
+    Ok(HttpResponse::Created().json(created))
+}
+
+// POST /auth/login
+async fn login(pool: web::Data<PgPool>, config: web::Data<Config>, body: web::Json<LoginUser>) -> Result<HttpResponse, Error> {
+    let user = db::get_user_by_email(pool.get_ref(), &body.email)
+        .await?
+        .ok_or(Error::Unauthorized("invalid credentials"))?;
+
+    if !password::verify(&body.password, &user.password_hash) {
+        return Err(Error::Unauthorized("invalid credentials"));
+    }
+
+    let token = auth::create_token(user.id, &config.jwt_secret, config.jwt_maxage)
+        .map_err(|_| Error::Validation("failed to create token".into()))?;
+
+    // Also open a server-side session so clients that prefer opaque cookies
+    // over a bearer token can authenticate without handling the JWT.
+    let session_token = session::Session::create(pool.get_ref(), user.id, config.jwt_maxage).await?;
+    let cookie = Cookie::build("session", session_token)
+        .http_only(true)
+        .path("/")
+        .finish();
+
+    Ok(HttpResponse::Ok().cookie(cookie).json(TokenResponse { token }))
+}
+
+// POST /auth/logout
+async fn logout(db: web::Data<PgPool>, req: actix_web::HttpRequest) -> Result<HttpResponse, Error> {
+    if let Some(cookie) = req.cookie("session") {
+        session::Session::delete(db.get_ref(), cookie.value()).await?;
+    }
+
+    let mut removal = Cookie::named("session");
+    removal.set_path("/");
+
+    Ok(HttpResponse::Ok()
+        .cookie(removal)
+        .body("Logged out"))
+}
+
+// GET /auth/me
+async fn me(pool: web::Data<PgPool>, session: Session) -> Result<HttpResponse, Error> {
+    let user = session.user(pool.get_ref()).await?;
+    Ok(HttpResponse::Ok().json(user))
 }
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     dotenv().ok();
-    let db_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
-    let pool = PgPool::connect(&db_url).await.expect("Failed to connect DB");
+    let config = Config::from_env();
+    let pool = PgPool::connect(&config.database_url).await.expect("Failed to connect DB");
 
     // Run migrations if not yet run
     sqlx::migrate!().run(&pool).await.expect("Migrations failed");
@@ -131,6 +190,11 @@ async fn main() -> std::io::Result<()> {
     HttpServer::new(move || {
         App::new()
             .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(config.clone()))
+            .route("/auth/register", web::post().to(register))
+            .route("/auth/login", web::post().to(login))
+            .route("/auth/logout", web::post().to(logout))
+            .route("/auth/me", web::get().to(me))
             .route("/users", web::get().to(get_users))
             .route("/users", web::post().to(create_user))
             .route("/users/{id}", web::get().to(get_user))
@@ -0,0 +1,22 @@
+use argon2::password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+
+// Hash a plaintext password into a PHC string suitable for storage. A fresh
+// random salt is generated for every call.
+pub fn hash(plaintext: &str) -> argon2::password_hash::Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(plaintext.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+}
+
+// Verify a plaintext password against a stored PHC hash, returning `false`
+// for malformed hashes as well as mismatches.
+pub fn verify(plaintext: &str, hash: &str) -> bool {
+    match PasswordHash::new(hash) {
+        Ok(parsed) => Argon2::default()
+            .verify_password(plaintext.as_bytes(), &parsed)
+            .is_ok(),
+        Err(_) => false,
+    }
+}
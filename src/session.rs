@@ -0,0 +1,152 @@
+use actix_web::{dev::Payload, web, FromRequest, HttpRequest};
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use chrono::{DateTime, Duration, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, PgPool};
+use std::future::Future;
+use std::pin::Pin;
+use uuid::Uuid;
+
+use crate::error::Error;
+use crate::User;
+
+// Length in bytes of a freshly generated session token. Hex-encoded this
+// yields a 64-character opaque string, comfortably above any guessable range.
+const TOKEN_BYTES: usize = 32;
+
+// Generate a cryptographically random opaque session token. Each call draws
+// fresh entropy from the OS RNG, so collisions are vanishingly unlikely.
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; TOKEN_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Deterministically hash a token for storage so a leaked database row cannot
+// be replayed as a cookie.
+pub fn hash_token(token: &str) -> String {
+    let digest = Sha256::digest(token.as_bytes());
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Verify a presented token against a stored hash.
+pub fn verify_token(token: &str, hash: &str) -> bool {
+    hash_token(token) == hash
+}
+
+// A server-side session row. `id` stores the hashed token, never the raw one.
+#[derive(Debug, FromRow)]
+pub struct Session {
+    pub id: String,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Session {
+    // Create a new session for `user_id`, valid for `maxage` seconds, and
+    // return the raw (un-hashed) token to hand back to the client.
+    pub async fn create(pool: &PgPool, user_id: Uuid, maxage: i64) -> Result<String, Error> {
+        let token = generate_token();
+        let now = Utc::now();
+        sqlx::query("INSERT INTO sessions (id, user_id, created_at, expires_at) VALUES ($1, $2, $3, $4)")
+            .bind(hash_token(&token))
+            .bind(user_id)
+            .bind(now)
+            .bind(now + Duration::seconds(maxage))
+            .execute(pool)
+            .await?;
+        Ok(token)
+    }
+
+    // Delete the session identified by a raw token.
+    pub async fn delete(pool: &PgPool, token: &str) -> Result<(), Error> {
+        sqlx::query("DELETE FROM sessions WHERE id = $1")
+            .bind(hash_token(token))
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at <= now
+    }
+}
+
+// Extractor that authenticates a request from the opaque `session` cookie,
+// rejecting missing, unknown, or expired sessions. It yields the session row
+// only; the owning user is not loaded here but by `Session::user`.
+impl FromRequest for Session {
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let pool = req.app_data::<web::Data<PgPool>>().cloned();
+        let token = req.cookie("session").map(|c| c.value().to_string());
+
+        Box::pin(async move {
+            let pool = pool.ok_or_else(|| actix_web::error::ErrorInternalServerError("missing pool"))?;
+            let token = token.ok_or_else(|| actix_web::error::ErrorUnauthorized("missing session cookie"))?;
+
+            let session = sqlx::query_as::<_, Session>("SELECT * FROM sessions WHERE id = $1")
+                .bind(hash_token(&token))
+                .fetch_optional(pool.get_ref())
+                .await
+                .map_err(|_| actix_web::error::ErrorInternalServerError("session lookup failed"))?
+                .ok_or_else(|| actix_web::error::ErrorUnauthorized("invalid session"))?;
+
+            if session.is_expired(Utc::now()) {
+                return Err(actix_web::error::ErrorUnauthorized("session expired"));
+            }
+
+            Ok(session)
+        })
+    }
+}
+
+// Resolve the `User` that owns this session.
+impl Session {
+    pub async fn user(&self, pool: &PgPool) -> Result<User, Error> {
+        User::get_id(pool, self.user_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_has_minimum_length_and_is_unique() {
+        let a = generate_token();
+        let b = generate_token();
+        assert_eq!(a.len(), TOKEN_BYTES * 2);
+        assert!(a.len() >= 32);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn hashed_token_round_trips() {
+        let token = generate_token();
+        let hash = hash_token(&token);
+        assert!(verify_token(&token, &hash));
+        assert!(!verify_token("not-the-token", &hash));
+    }
+
+    #[test]
+    fn expired_sessions_are_rejected() {
+        let now = Utc::now();
+        let session = Session {
+            id: "x".into(),
+            user_id: Uuid::nil(),
+            created_at: now - Duration::seconds(120),
+            expires_at: now - Duration::seconds(60),
+        };
+        assert!(session.is_expired(now));
+
+        let live = Session {
+            expires_at: now + Duration::seconds(60),
+            ..session
+        };
+        assert!(!live.is_expired(now));
+    }
+}